@@ -1,6 +1,8 @@
 //! rust-pssh: A high-performance parallel SSH executor with JSON logging and timing support
 
-use openssh::{KnownHosts, SessionBuilder};
+use openssh::{KnownHosts, SessionBuilder, Stdio};
+use rand::Rng;
+use tokio::process::Command as ScpCommand;
 use tokio::sync::Semaphore;
 use futures::stream::{FuturesUnordered, StreamExt};
 use std::sync::Arc;
@@ -11,6 +13,166 @@ use clap::{Arg, ArgAction, Command};
 use serde::Serialize;
 use std::sync::Mutex;
 use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// What each host task should do: run a remote command, or push/pull a file
+// via `scp`.
+#[derive(Clone)]
+enum Mode {
+    Exec(String),
+    CopyTo { local: String, remote: String },
+    CopyFrom { remote: String, local: String },
+}
+
+// How completed HostResults are written: one big pretty array at the end,
+// or one compact line per host as soon as it finishes.
+#[derive(Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Json,
+    Ndjson,
+}
+
+// Running counts shown on stderr as hosts finish, so a long run isn't silent.
+#[derive(Clone, Default)]
+struct Progress {
+    completed: Arc<AtomicUsize>,
+    succeeded: Arc<AtomicUsize>,
+    failed: Arc<AtomicUsize>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Progress {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn report(&self, total: usize, success: bool) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+        let completed = self.completed.fetch_add(1, Ordering::Relaxed) + 1;
+        if success {
+            self.succeeded.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+
+        eprint!(
+            "\rcompleted {}/{} succeeded {} failed {} in-flight {}    ",
+            completed,
+            total,
+            self.succeeded.load(Ordering::Relaxed),
+            self.failed.load(Ordering::Relaxed),
+            self.in_flight.load(Ordering::Relaxed),
+        );
+    }
+}
+
+// Expands `{host}`, `{index}`, `{n}`, and `{env:VAR}` placeholders in a
+// command template so each host in the fleet can run a slightly different
+// command from a single --command string. `index`/`n` are the host's
+// position and size within the *whole* hostfile, not just this jump host's
+// slice, so a `--shard {index}` out of `{n}` pattern stays collision-free
+// even when --total splits the run across multiple jump hosts.
+fn expand_command(template: &str, host: &str, index: usize, n: usize) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else {
+            out.push_str(rest);
+            rest = "";
+            break;
+        };
+
+        let placeholder = &rest[1..end];
+        match placeholder {
+            "host" => out.push_str(host),
+            "index" => out.push_str(&index.to_string()),
+            "n" => out.push_str(&n.to_string()),
+            other => match other.strip_prefix("env:") {
+                Some(var) => out.push_str(&std::env::var(var).unwrap_or_default()),
+                None => out.push_str(&rest[..end + 1]),
+            },
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+// Parses a host line of the form `[user@]host[:port]` into its bare host and
+// any per-host overrides, letting individual lines deviate from the global
+// --user/--port flags. IPv6 literals need bracket notation (`[::1]:2222`) to
+// carry a port, same as `ssh`/`scp` require, since a bare address is already
+// full of colons.
+fn parse_host_spec(line: &str) -> (String, Option<String>, Option<u16>) {
+    let (user, rest) = match line.split_once('@') {
+        Some((u, r)) => (Some(u.to_string()), r),
+        None => (None, line),
+    };
+
+    if let Some(after_bracket) = rest.strip_prefix('[') {
+        if let Some(end) = after_bracket.find(']') {
+            let host = &after_bracket[..end];
+            let port = after_bracket[end + 1..]
+                .strip_prefix(':')
+                .and_then(|p| p.parse::<u16>().ok());
+            return (host.to_string(), user, port);
+        }
+    }
+
+    // Only treat a trailing `:NNN` as a port when there's exactly one colon;
+    // a bare IPv6 literal like `::1` has several and must pass through whole.
+    if rest.matches(':').count() == 1 {
+        if let Some((h, p)) = rest.rsplit_once(':') {
+            if let Ok(port) = p.parse::<u16>() {
+                return (h.to_string(), user, Some(port));
+            }
+        }
+    }
+
+    (rest.to_string(), user, None)
+}
+
+// Builds the `scp` flags that mirror the SessionBuilder options used for
+// command execution, since file transfers shell out to the system `scp`
+// instead of going through the openssh session (openssh dropped SFTP
+// support; there's no channel to reuse here).
+fn scp_args(
+    port: Option<u16>,
+    identity_file: &Option<String>,
+    jump_host: &Option<String>,
+    user: &Option<String>,
+) -> Vec<String> {
+    let mut args = vec![
+        "-o".to_string(),
+        "StrictHostKeyChecking=accept-new".to_string(),
+        "-o".to_string(),
+        "BatchMode=yes".to_string(),
+    ];
+    if let Some(port) = port {
+        args.push("-P".to_string());
+        args.push(port.to_string());
+    }
+    if let Some(identity_file) = identity_file {
+        args.push("-i".to_string());
+        args.push(identity_file.clone());
+    }
+    if let Some(jump_host) = jump_host {
+        args.push("-J".to_string());
+        args.push(jump_host.clone());
+    }
+    if let Some(user) = user {
+        args.push("-o".to_string());
+        args.push(format!("User={}", user));
+    }
+    args
+}
 
 // Struct to hold the result of each SSH command
 #[derive(Serialize)]
@@ -20,6 +182,46 @@ struct HostResult {
     duration_ms: u128,
     output: Option<String>,
     error: Option<String>,
+    exit_code: Option<i32>,
+    stderr: Option<String>,
+    bytes_transferred: Option<u64>,
+    throughput_mb_s: Option<f64>,
+    expanded_command: Option<String>,
+    attempts: u32,
+}
+
+// What a single connect+exec attempt produced, before the host/duration/
+// attempt count (which only the retry loop knows) are folded in.
+struct AttemptOutcome {
+    status: String,
+    output: Option<String>,
+    exit_code: Option<i32>,
+    stderr: Option<String>,
+    bytes_transferred: Option<u64>,
+    throughput_mb_s: Option<f64>,
+    expanded_command: Option<String>,
+}
+
+// Computes the full-jitter backoff delay for retry attempt `attempt` (1-based):
+// a random value in [0, min(base * 2^(attempt-1), max_delay)].
+fn backoff_delay(attempt: u32, base_ms: u64, max_delay_ms: u64) -> Duration {
+    let exp_delay = base_ms.saturating_mul(1u64 << (attempt - 1).min(63));
+    let capped = exp_delay.min(max_delay_ms);
+    let jittered = if capped == 0 {
+        0
+    } else {
+        rand::thread_rng().gen_range(0..=capped)
+    };
+    Duration::from_millis(jittered)
+}
+
+// Computes a transfer's throughput in MB/s from the bytes moved and the
+// wall-clock duration of the task.
+fn throughput_mb_s(bytes: u64, duration_ms: u128) -> f64 {
+    if duration_ms == 0 {
+        return 0.0;
+    }
+    (bytes as f64 / 1_000_000.0) / (duration_ms as f64 / 1_000.0)
 }
 
 #[tokio::main]
@@ -38,8 +240,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .short('c')
             .long("command")
             .action(ArgAction::Set)
-            .required(true)
+            .required(false)
             .help("Command to run on each host"))
+        .arg(Arg::new("copy-to")
+            .long("copy-to")
+            .num_args(2)
+            .value_names(["LOCAL", "REMOTE"])
+            .required(false)
+            .help("Push LOCAL to REMOTE on every host instead of running a command"))
+        .arg(Arg::new("copy-from")
+            .long("copy-from")
+            .num_args(2)
+            .value_names(["REMOTE", "LOCAL"])
+            .required(false)
+            .help("Pull REMOTE from every host into LOCAL/<host>/ instead of running a command"))
         .arg(Arg::new("concurrency")
             .short('n')
             .long("concurrency")
@@ -73,92 +287,408 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .required(false)
             .default_value("1")
             .help("Total number of jump hosts to split work between"))
+        .arg(Arg::new("user")
+            .short('u')
+            .long("user")
+            .action(ArgAction::Set)
+            .required(false)
+            .help("Login user for every host (overridden by a user@host line)"))
+        .arg(Arg::new("port")
+            .short('p')
+            .long("port")
+            .action(ArgAction::Set)
+            .required(false)
+            .help("SSH port for every host (overridden by a host:port line)"))
+        .arg(Arg::new("identity-file")
+            .short('i')
+            .long("identity-file")
+            .action(ArgAction::Set)
+            .required(false)
+            .help("Path to a private key to authenticate with"))
+        .arg(Arg::new("jump-host")
+            .short('J')
+            .long("jump-host")
+            .action(ArgAction::Set)
+            .required(false)
+            .help("Host to use as a ProxyJump for every connection"))
+        .arg(Arg::new("retries")
+            .long("retries")
+            .action(ArgAction::Set)
+            .required(false)
+            .default_value("0")
+            .help("Number of times to retry a host's connect+exec after a transient failure"))
+        .arg(Arg::new("retry-backoff")
+            .long("retry-backoff")
+            .action(ArgAction::Set)
+            .required(false)
+            .default_value("200")
+            .help("Base delay in milliseconds for exponential backoff between retries"))
+        .arg(Arg::new("retry-max-delay")
+            .long("retry-max-delay")
+            .action(ArgAction::Set)
+            .required(false)
+            .default_value("30000")
+            .help("Maximum backoff delay in milliseconds before jitter is applied"))
+        .arg(Arg::new("output-format")
+            .long("output-format")
+            .action(ArgAction::Set)
+            .required(false)
+            .default_value("json")
+            .value_parser(["json", "ndjson"])
+            .help("json writes one pretty array at the end; ndjson streams one result per line as hosts finish"))
+        .arg(Arg::new("pty")
+            .long("pty")
+            .action(ArgAction::SetTrue)
+            .help("Request a pseudo-terminal for the remote command (not supported over openssh's multiplexed connection; fails fast instead of running without one)"))
+        .arg(Arg::new("stdin")
+            .long("stdin")
+            .action(ArgAction::Set)
+            .required(false)
+            .help("Path to a file whose contents are piped to the remote command's stdin"))
         .get_matches();
 
     // Extract argument values
     let hostfile = matches.get_one::<String>("hostfile").unwrap();
-    let command = matches.get_one::<String>("command").unwrap();
+    let mode = match (
+        matches.get_one::<String>("command"),
+        matches.get_many::<String>("copy-to"),
+        matches.get_many::<String>("copy-from"),
+    ) {
+        (Some(cmd), None, None) => Mode::Exec(cmd.to_string()),
+        (None, Some(mut args), None) => {
+            let local = args.next().unwrap().to_string();
+            let remote = args.next().unwrap().to_string();
+            Mode::CopyTo { local, remote }
+        }
+        (None, None, Some(mut args)) => {
+            let remote = args.next().unwrap().to_string();
+            let local = args.next().unwrap().to_string();
+            Mode::CopyFrom { remote, local }
+        }
+        _ => return Err("exactly one of --command, --copy-to, or --copy-from is required".into()),
+    };
     let concurrency: usize = matches.get_one::<String>("concurrency").unwrap().parse()?;
     let timeout_secs: u64 = matches.get_one::<String>("timeout").unwrap().parse()?;
     let logfile = matches.get_one::<String>("logfile").unwrap();
     let index: usize = matches.get_one::<String>("index").unwrap().parse()?;
     let total: usize = matches.get_one::<String>("total").unwrap().parse()?;
+    let default_user = matches.get_one::<String>("user").cloned();
+    let default_port: Option<u16> = matches
+        .get_one::<String>("port")
+        .map(|p| p.parse())
+        .transpose()?;
+    let identity_file = matches.get_one::<String>("identity-file").cloned();
+    let jump_host = matches.get_one::<String>("jump-host").cloned();
+    let retries: u32 = matches.get_one::<String>("retries").unwrap().parse()?;
+    let retry_backoff_ms: u64 = matches.get_one::<String>("retry-backoff").unwrap().parse()?;
+    let retry_max_delay_ms: u64 = matches.get_one::<String>("retry-max-delay").unwrap().parse()?;
+    let output_format = match matches.get_one::<String>("output-format").unwrap().as_str() {
+        "ndjson" => OutputFormat::Ndjson,
+        _ => OutputFormat::Json,
+    };
+    let pty = matches.get_flag("pty");
+    let stdin_file = matches.get_one::<String>("stdin").cloned();
 
     // Open and read the list of hosts
     let file = File::open(hostfile)?;
     let reader = BufReader::new(file);
-    let all_hosts: Vec<String> = reader.lines().filter_map(Result::ok).collect();
+    let all_hosts: Vec<String> = reader.lines().map_while(Result::ok).collect();
 
-    // Filter hosts based on jump host index (distribute work)
-    let filtered_hosts: Vec<String> = all_hosts
+    // Filter hosts based on jump host index (distribute work), keeping each
+    // host's position in the *full* hostfile so `{index}`/`{n}` stay
+    // fleet-global and usable for sharding even when --total splits the run
+    // across several jump hosts.
+    let fleet_size = all_hosts.len();
+    let filtered_hosts: Vec<(usize, String)> = all_hosts
         .into_iter()
         .enumerate()
         .filter(|(i, _)| i % total == index)
-        .map(|(_, h)| h)
         .collect();
 
     // Shared semaphore to limit concurrent SSH sessions
     let semaphore = Arc::new(Semaphore::new(concurrency));
 
-    // Shared thread-safe result buffer
+    // Shared thread-safe result buffer, used only for the buffered "json" format
     let results = Arc::new(Mutex::new(VecDeque::new()));
 
+    // For "ndjson", each host's result is appended to the logfile as soon as
+    // it's ready instead of being held in memory until the run finishes.
+    let ndjson_file = match output_format {
+        OutputFormat::Ndjson => Some(Arc::new(Mutex::new(File::create(logfile)?))),
+        OutputFormat::Json => None,
+    };
+
+    let progress = Progress::new();
+
     // Track all ongoing SSH futures
     let mut futures = FuturesUnordered::new();
 
-    // Spawn async SSH tasks for each assigned host
-    for host in filtered_hosts {
-        let cmd = command.to_string();
+    // Spawn async SSH tasks for each assigned host. `shard_count` is this
+    // jump host's slice, used for the live progress line; `host_index` is
+    // this host's position in the full hostfile, used for `{index}`/`{n}`.
+    let shard_count = filtered_hosts.len();
+    for (host_index, host) in filtered_hosts.into_iter() {
+        let (bare_host, host_user, host_port) = parse_host_spec(&host);
+        let user = host_user.or_else(|| default_user.clone());
+        let port = host_port.or(default_port);
+        let identity_file = identity_file.clone();
+        let jump_host = jump_host.clone();
+
+        let mode = mode.clone();
         let semaphore = semaphore.clone();
         let results_clone = results.clone();
+        let ndjson_file = ndjson_file.clone();
+        let progress = progress.clone();
         let host_clone = host.clone();
+        // A --pty refusal is not transient, so it is not retried.
+        let max_attempts = if pty && matches!(mode, Mode::Exec(_)) { 1 } else { retries + 1 };
+        let stdin_file = stdin_file.clone();
 
         futures.push(tokio::spawn(async move {
             let permit = semaphore.acquire_owned().await.unwrap();
+            progress.in_flight.fetch_add(1, Ordering::Relaxed);
             let start_time = Instant::now();
 
-            let res = tokio::time::timeout(Duration::from_secs(timeout_secs), async {
-                let session = SessionBuilder::default()
-                    .known_hosts_check(KnownHosts::Accept)
-                    .connect(&host_clone)
-                    .await?;
-
-                let output = session.command(cmd).output().await?;
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                session.close().await?;
-
-                results_clone.lock().unwrap().push_back(HostResult {
-                    host: host_clone.clone(),
-                    status: "success".to_string(),
-                    duration_ms: start_time.elapsed().as_millis(),
-                    output: Some(stdout),
-                    error: None,
-                });
-                Ok::<_, Box<dyn std::error::Error>>(())
-            }).await;
+            let mut attempt = 0u32;
+            let mut last_error = String::new();
+            let mut outcome: Option<AttemptOutcome> = None;
+
+            while attempt < max_attempts {
+                attempt += 1;
+
+                let user = user.clone();
+                let port = port;
+                let identity_file = identity_file.clone();
+                let jump_host = jump_host.clone();
+                let mode = mode.clone();
+                let bare_host = bare_host.clone();
+
+                let attempt_result = tokio::time::timeout(Duration::from_secs(timeout_secs), async {
+                    let outcome = match mode {
+                        Mode::Exec(cmd) => {
+                            if pty {
+                                return Err(
+                                    "--pty is not supported: openssh 0.10 has no PTY \
+                                     allocation over its multiplexed connection"
+                                        .into(),
+                                );
+                            }
+
+                            let mut builder = SessionBuilder::default();
+                            builder.known_hosts_check(KnownHosts::Accept);
+                            if let Some(user) = user {
+                                builder.user(user);
+                            }
+                            if let Some(port) = port {
+                                builder.port(port);
+                            }
+                            if let Some(identity_file) = identity_file {
+                                builder.keyfile(identity_file);
+                            }
+                            if let Some(jump_host) = jump_host {
+                                builder.jump_hosts([jump_host]);
+                            }
+
+                            let session = builder.connect(&bare_host).await?;
+
+                            let expanded = expand_command(&cmd, &bare_host, host_index, fleet_size);
+                            let mut remote_command = session.command(expanded.clone());
+
+                            let output = match &stdin_file {
+                                Some(path) => {
+                                    let mut input = tokio::fs::File::open(path).await?;
+                                    remote_command.stdin(Stdio::piped());
+                                    let mut child = remote_command.spawn().await?;
+                                    let mut child_stdin =
+                                        child.stdin().take().expect("stdin was requested");
+                                    tokio::io::copy(&mut input, &mut child_stdin).await?;
+                                    drop(child_stdin);
+                                    child.wait_with_output().await?
+                                }
+                                None => remote_command.output().await?,
+                            };
+
+                            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+                            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+                            let exit_code = output.status.code();
+                            session.close().await?;
+
+                            let status = if output.status.success() {
+                                "success"
+                            } else {
+                                "failed"
+                            };
+
+                            AttemptOutcome {
+                                status: status.to_string(),
+                                output: Some(stdout),
+                                exit_code,
+                                stderr: Some(stderr),
+                                bytes_transferred: None,
+                                throughput_mb_s: None,
+                                expanded_command: Some(expanded),
+                            }
+                        }
+                        Mode::CopyTo { local, remote } => {
+                            let remote_spec = format!("{}:{}", bare_host, remote);
+                            let args = scp_args(port, &identity_file, &jump_host, &user);
+                            let output = ScpCommand::new("scp")
+                                .args(&args)
+                                .arg(&local)
+                                .arg(&remote_spec)
+                                .kill_on_drop(true)
+                                .output()
+                                .await?;
+                            if !output.status.success() {
+                                return Err(format!(
+                                    "scp {} -> {} failed: {}",
+                                    local,
+                                    remote_spec,
+                                    String::from_utf8_lossy(&output.stderr)
+                                )
+                                .into());
+                            }
+                            let bytes = tokio::fs::metadata(&local).await?.len();
+
+                            AttemptOutcome {
+                                status: "success".to_string(),
+                                output: None,
+                                exit_code: None,
+                                stderr: None,
+                                bytes_transferred: Some(bytes),
+                                throughput_mb_s: None,
+                                expanded_command: None,
+                            }
+                        }
+                        Mode::CopyFrom { remote, local } => {
+                            let host_dir = Path::new(&local).join(&bare_host);
+                            tokio::fs::create_dir_all(&host_dir).await?;
+                            let file_name = Path::new(&remote)
+                                .file_name()
+                                .ok_or("remote path has no file name")?;
+                            let dest = host_dir.join(file_name);
+
+                            let remote_spec = format!("{}:{}", bare_host, remote);
+                            let args = scp_args(port, &identity_file, &jump_host, &user);
+                            let output = ScpCommand::new("scp")
+                                .args(&args)
+                                .arg(&remote_spec)
+                                .arg(&dest)
+                                .kill_on_drop(true)
+                                .output()
+                                .await?;
+                            if !output.status.success() {
+                                return Err(format!(
+                                    "scp {} -> {} failed: {}",
+                                    remote_spec,
+                                    dest.display(),
+                                    String::from_utf8_lossy(&output.stderr)
+                                )
+                                .into());
+                            }
+                            let bytes = tokio::fs::metadata(&dest).await?.len();
+
+                            AttemptOutcome {
+                                status: "success".to_string(),
+                                output: None,
+                                exit_code: None,
+                                stderr: None,
+                                bytes_transferred: Some(bytes),
+                                throughput_mb_s: None,
+                                expanded_command: None,
+                            }
+                        }
+                    };
+
+                    Ok::<_, Box<dyn std::error::Error + Send + Sync>>(outcome)
+                })
+                .await;
+
+                match attempt_result {
+                    Ok(Ok(result)) => {
+                        outcome = Some(result);
+                        break;
+                    }
+                    Ok(Err(e)) => last_error = format!("{}", e),
+                    Err(elapsed) => last_error = format!("{:?}", elapsed),
+                }
+
+                if attempt < max_attempts {
+                    let delay = backoff_delay(attempt, retry_backoff_ms, retry_max_delay_ms);
+                    tokio::time::sleep(delay).await;
+                }
+            }
 
             drop(permit);
 
-            if let Err(e) = res {
-                results_clone.lock().unwrap().push_back(HostResult {
-                    host: host_clone.clone(),
-                    status: "error".to_string(),
-                    duration_ms: start_time.elapsed().as_millis(),
-                    output: None,
-                    error: Some(format!("{:?}", e)),
-                });
+            let duration_ms = start_time.elapsed().as_millis();
+            let (result, success) = match outcome {
+                Some(mut o) => {
+                    if let Some(bytes) = o.bytes_transferred {
+                        o.throughput_mb_s = Some(throughput_mb_s(bytes, duration_ms));
+                    }
+                    let success = o.status == "success";
+                    let result = HostResult {
+                        host: host_clone.clone(),
+                        status: o.status,
+                        duration_ms,
+                        output: o.output,
+                        error: None,
+                        exit_code: o.exit_code,
+                        stderr: o.stderr,
+                        bytes_transferred: o.bytes_transferred,
+                        throughput_mb_s: o.throughput_mb_s,
+                        expanded_command: o.expanded_command,
+                        attempts: attempt,
+                    };
+                    (result, success)
+                }
+                None => {
+                    let result = HostResult {
+                        host: host_clone.clone(),
+                        status: "error".to_string(),
+                        duration_ms,
+                        output: None,
+                        error: Some(last_error),
+                        exit_code: None,
+                        stderr: None,
+                        bytes_transferred: None,
+                        throughput_mb_s: None,
+                        expanded_command: None,
+                        attempts: attempt,
+                    };
+                    (result, false)
+                }
+            };
+
+            progress.report(shard_count, success);
+
+            match &ndjson_file {
+                Some(ndjson_file) => {
+                    let line = serde_json::to_string(&result).unwrap();
+                    let mut f = ndjson_file.lock().unwrap();
+                    writeln!(f, "{}", line).unwrap();
+                }
+                None => {
+                    results_clone.lock().unwrap().push_back(result);
+                }
             }
         }));
     }
 
     // Await all SSH tasks to complete
-    while let Some(_) = futures.next().await {}
+    while futures.next().await.is_some() {}
+    eprintln!();
 
-    // Write results to JSON file
-    let collected = results.lock().unwrap();
-    let json = serde_json::to_string_pretty(&*collected)?;
-    let mut file = File::create(logfile)?;
-    file.write_all(json.as_bytes())?;
+    match output_format {
+        OutputFormat::Json => {
+            let collected = results.lock().unwrap();
+            let json = serde_json::to_string_pretty(&*collected)?;
+            let mut file = File::create(logfile)?;
+            file.write_all(json.as_bytes())?;
+        }
+        OutputFormat::Ndjson => {}
+    }
 
     println!("Results written to {}", logfile);
 